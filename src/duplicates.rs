@@ -0,0 +1,187 @@
+//! Duplicate-file detection built on top of [`Search`](crate::Search).
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+
+use crate::Item;
+use crate::ItemMetadata;
+use crate::ItemType;
+use crate::Search;
+
+/// The number of bytes hashed from the start and end of a file when
+/// prefiltering candidates, before committing to a full content hash.
+const PREFILTER_CHUNK_SIZE: u64 = 4096;
+
+/// The size of the buffer used when streaming a file's content into a
+/// [`DefaultHasher`].
+const READ_BUFFER_SIZE: usize = 8192;
+
+/// Finds duplicate files among the results of `search`.
+///
+/// Items are first bucketed by size, since files with a unique size can't
+/// have duplicates; those buckets are discarded without touching the disk.
+/// Surviving candidates are regrouped by a cheap prefilter hash over the
+/// first and last [`PREFILTER_CHUNK_SIZE`] bytes of each file, regrouped
+/// again by a full content hash, and finally confirmed with a byte-for-byte
+/// comparison, since a 64-bit hash match alone isn't a strong enough
+/// guarantee to treat a set as safe to deduplicate. Folders, volumes and
+/// zero-byte files are skipped. Files that can't be opened or read are
+/// logged via `log::error!` and excluded from the results, rather than
+/// aborting the whole scan.
+///
+/// Returns one inner `Vec<Item>` per confirmed, byte-identical duplicate set.
+#[must_use]
+pub fn find_duplicates(search: &Search) -> Vec<Vec<Item>> {
+    let search = search.clone().request_metadata(ItemMetadata::SIZE);
+
+    let mut by_size: HashMap<u64, Vec<Item>> = HashMap::new();
+    for item in search.query_all() {
+        if item.item_type != ItemType::File {
+            continue;
+        }
+        let Some(size) = item.size.filter(|&size| size > 0) else {
+            continue;
+        };
+        by_size.entry(size).or_default().push(item);
+    }
+
+    by_size
+        .into_iter()
+        .filter(|(_, items)| items.len() > 1)
+        .flat_map(|(size, items)| find_duplicates_among(size, items))
+        .collect()
+}
+
+/// Confirms duplicates within a single size bucket, via a cheap prefilter
+/// hash, a full content hash, and a final byte-for-byte comparison.
+fn find_duplicates_among(size: u64, candidates: Vec<Item>) -> Vec<Vec<Item>> {
+    let mut by_prefilter: HashMap<u64, Vec<Item>> = HashMap::new();
+    for item in candidates {
+        match prefilter_hash(&item.path, size) {
+            Ok(hash) => by_prefilter.entry(hash).or_default().push(item),
+            Err(err) => log_read_error(&item, err),
+        }
+    }
+
+    by_prefilter
+        .into_values()
+        .filter(|items| items.len() > 1)
+        .flat_map(|candidates| {
+            let mut by_hash: HashMap<u64, Vec<Item>> = HashMap::new();
+            for item in candidates {
+                match full_hash(&item.path) {
+                    Ok(hash) => by_hash.entry(hash).or_default().push(item),
+                    Err(err) => log_read_error(&item, err),
+                }
+            }
+            by_hash
+                .into_values()
+                .filter(|items| items.len() > 1)
+                .flat_map(confirm_byte_identical)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Splits `candidates` (all sharing a full content hash) into groups that are
+/// actually byte-for-byte identical, since a hash match alone can't rule out
+/// a collision.
+fn confirm_byte_identical(candidates: Vec<Item>) -> Vec<Vec<Item>> {
+    let mut groups: Vec<Vec<Item>> = Vec::new();
+
+    'candidates: for item in candidates {
+        for group in &mut groups {
+            match files_equal(&group[0].path, &item.path) {
+                Ok(true) => {
+                    group.push(item);
+                    continue 'candidates;
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    log_read_error(&item, err);
+                    continue 'candidates;
+                }
+            }
+        }
+        groups.push(vec![item]);
+    }
+
+    groups.into_iter().filter(|group| group.len() > 1).collect()
+}
+
+/// Compares the full contents of the files at `left` and `right` for exact
+/// equality.
+fn files_equal(left: &Path, right: &Path) -> std::io::Result<bool> {
+    let mut left_file = File::open(left)?;
+    let mut right_file = File::open(right)?;
+    let mut left_buf = [0_u8; READ_BUFFER_SIZE];
+    let mut right_buf = [0_u8; READ_BUFFER_SIZE];
+
+    loop {
+        let left_read = left_file.read(&mut left_buf)?;
+        let right_read = right_file.read(&mut right_buf)?;
+        if left_read != right_read || left_buf[..left_read] != right_buf[..right_read] {
+            return Ok(false);
+        }
+        if left_read == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Hashes the first and last [`PREFILTER_CHUNK_SIZE`] bytes of the file at
+/// `path`, used as a cheap prefilter before a full content hash.
+///
+/// Hashed with [`DefaultHasher`] rather than a dedicated content-hashing
+/// crate, since this repository currently has no manifest to declare such a
+/// dependency in.
+fn prefilter_hash(path: &Path, size: u64) -> std::io::Result<u64> {
+    let mut file = File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+
+    let head_len = PREFILTER_CHUNK_SIZE.min(size) as usize;
+    let mut head = vec![0_u8; head_len];
+    file.read_exact(&mut head)?;
+    hasher.write(&head);
+
+    if size > PREFILTER_CHUNK_SIZE {
+        let tail_len = PREFILTER_CHUNK_SIZE as usize;
+        file.seek(SeekFrom::End(-(tail_len as i64)))?;
+        let mut tail = vec![0_u8; tail_len];
+        file.read_exact(&mut tail)?;
+        hasher.write(&tail);
+    }
+
+    Ok(hasher.finish())
+}
+
+/// Computes a full content hash of the file at `path`.
+fn full_hash(path: &Path) -> std::io::Result<u64> {
+    let mut file = File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buf = [0_u8; READ_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.write(&buf[..read]);
+    }
+    Ok(hasher.finish())
+}
+
+/// Logs a file that couldn't be read while searching for duplicates.
+fn log_read_error(item: &Item, err: std::io::Error) {
+    log::error!(
+        concat!(
+            "Unable to read {} while looking for duplicates. ",
+            "Caused by the following error: {}"),
+        item.path.display(),
+        err);
+}
@@ -0,0 +1,232 @@
+//! A typed, composable alternative to hand-writing Everything search syntax.
+
+use std::ffi::OsString;
+use std::ops::Bound;
+use std::ops::BitAnd;
+use std::ops::BitOr;
+use std::ops::Not;
+use std::ops::RangeBounds;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use crate::ItemType;
+
+/// A composable filter that compiles down to the Everything search syntax.
+///
+/// Build filters with the associated functions below (e.g. [`Filter::extension`],
+/// [`Filter::size`]) and combine them with `&`, `|` and `!` instead of
+/// hand-writing Everything's raw query syntax. A [`Filter`] can be passed
+/// anywhere a search pattern is expected (e.g. [`crate::search`]), since it
+/// implements `Into<OsString>`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use everything::Filter;
+/// use everything::ItemType;
+///
+/// let filter =
+///     Filter::extension("rs")
+///     & Filter::only(ItemType::File)
+///     & !Filter::content("TODO");
+/// let results = everything::search(filter).query_all();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Filter {
+    /// Matches items with the given file extension.
+    Extension(OsString),
+
+    /// Matches items whose size in bytes falls within the given bounds.
+    Size(Bound<u64>, Bound<u64>),
+
+    /// Matches items modified on or after the date (in UTC) of the given
+    /// time; the time of day is not taken into account.
+    ModifiedAfter(SystemTime),
+
+    /// Matches items whose content contains the given text.
+    Content(OsString),
+
+    /// Matches only items of the given type.
+    Only(ItemType),
+
+    /// An escape hatch for raw Everything search syntax, spliced in as-is.
+    Raw(OsString),
+
+    /// Matches items satisfying both of the given filters.
+    And(Box<Filter>, Box<Filter>),
+
+    /// Matches items satisfying either of the given filters.
+    Or(Box<Filter>, Box<Filter>),
+
+    /// Matches items not satisfying the given filter.
+    Not(Box<Filter>),
+}
+
+/// Constructors for the individual [`Filter`] combinators.
+impl Filter {
+    /// Matches items with the given file extension (without a leading dot).
+    #[must_use]
+    pub fn extension<S: Into<OsString>>(extension: S) -> Self {
+        Self::Extension(extension.into())
+    }
+
+    /// Matches items whose size in bytes falls within the given bounds.
+    #[must_use]
+    pub fn size<R: RangeBounds<u64>>(range: R) -> Self {
+        let clone_bound = |bound: Bound<&u64>| match bound {
+            Bound::Included(&size) => Bound::Included(size),
+            Bound::Excluded(&size) => Bound::Excluded(size),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        Self::Size(clone_bound(range.start_bound()), clone_bound(range.end_bound()))
+    }
+
+    /// Matches items modified on or after the date (in UTC) of the given
+    /// time; the time of day is not taken into account.
+    #[must_use]
+    pub const fn modified_after(time: SystemTime) -> Self {
+        Self::ModifiedAfter(time)
+    }
+
+    /// Matches items whose content contains the given text.
+    #[must_use]
+    pub fn content<S: Into<OsString>>(text: S) -> Self {
+        Self::Content(text.into())
+    }
+
+    /// Matches only items of the given type.
+    ///
+    /// Everything's search syntax has dedicated `file:` and `folder:` macros,
+    /// but no macro for matching only [`ItemType::Volume`]; that case falls
+    /// back to `folder:`, which will also match regular folders rather than
+    /// volumes exclusively.
+    #[must_use]
+    pub const fn only(item_type: ItemType) -> Self {
+        Self::Only(item_type)
+    }
+
+    /// An escape hatch for raw Everything search syntax, spliced in as-is.
+    #[must_use]
+    pub fn raw<S: Into<OsString>>(pattern: S) -> Self {
+        Self::Raw(pattern.into())
+    }
+
+    /// Compiles this filter down to an Everything search syntax string.
+    fn to_query_string(&self) -> String {
+        match self {
+            Self::Extension(extension) =>
+                format!("ext:{}", quote(&extension.to_string_lossy())),
+            Self::Size(start, end) =>
+                format_size(*start, *end),
+            Self::ModifiedAfter(time) =>
+                format!("dm:>={}", format_date(*time)),
+            Self::Content(text) =>
+                format!("content:{}", quote(&text.to_string_lossy())),
+            Self::Only(item_type) =>
+                match item_type {
+                    ItemType::File => "file:".to_owned(),
+                    // Everything has no dedicated volume/drive macro; this is
+                    // the closest available approximation. See `Filter::only`.
+                    ItemType::Folder | ItemType::Volume => "folder:".to_owned(),
+                },
+            Self::Raw(pattern) =>
+                pattern.to_string_lossy().into_owned(),
+            Self::And(left, right) =>
+                format!("({}) AND ({})", left.to_query_string(), right.to_query_string()),
+            Self::Or(left, right) =>
+                format!("({}) OR ({})", left.to_query_string(), right.to_query_string()),
+            Self::Not(inner) =>
+                format!("NOT ({})", inner.to_query_string()),
+        }
+    }
+}
+
+impl BitAnd for Filter {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self::And(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl BitOr for Filter {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self::Or(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl Not for Filter {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+}
+
+impl From<Filter> for OsString {
+    fn from(filter: Filter) -> Self {
+        Self::from(filter.to_query_string())
+    }
+}
+
+/// Quotes `value` if it contains whitespace, since Everything otherwise
+/// treats whitespace as separating distinct search terms.
+fn quote(value: &str) -> String {
+    if value.chars().any(char::is_whitespace) {
+        format!("\"{value}\"")
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Renders a size bound pair as an Everything `size:` constraint, e.g.
+/// `size:>=1024 <=2048`.
+fn format_size(start: Bound<u64>, end: Bound<u64>) -> String {
+    let mut constraints = Vec::new();
+    match start {
+        Bound::Included(size) => constraints.push(format!(">={size}")),
+        Bound::Excluded(size) => constraints.push(format!(">{size}")),
+        Bound::Unbounded => {}
+    }
+    match end {
+        Bound::Included(size) => constraints.push(format!("<={size}")),
+        Bound::Excluded(size) => constraints.push(format!("<{size}")),
+        Bound::Unbounded => {}
+    }
+
+    if constraints.is_empty() {
+        // Both bounds unbounded: the bare "size:" constraint Everything
+        // syntax would otherwise produce is invalid. ">=0" matches every
+        // item instead, faithfully representing an unconstrained range.
+        constraints.push(">=0".to_owned());
+    }
+
+    format!("size:{}", constraints.join(" "))
+}
+
+/// Renders a [`SystemTime`] as an Everything date (`YYYY-MM-DD`).
+fn format_date(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or(Duration::ZERO);
+    let days_since_epoch = (since_epoch.as_secs() / 86_400) as i64;
+    let (year, month, day) = civil_from_days(days_since_epoch);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Converts a day count since the Unix epoch (1970-01-01) into a
+/// `(year, month, day)` civil date, using Howard Hinnant's
+/// `civil_from_days` algorithm.
+const fn civil_from_days(days_since_epoch: i64) -> (i64, u32, u32) {
+    let z = days_since_epoch + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let day_of_era = (z - era * 146_097) as u64;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let year = year_of_era as i64 + era * 400;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
@@ -6,10 +6,17 @@ use std::ops::RangeBounds;
 use std::path::Path;
 use std::path::PathBuf;
 use std::time::Duration;
+use std::time::Instant;
 use std::time::SystemTime;
 
 use everything_sdk::*;
 
+mod filter;
+
+pub mod duplicates;
+
+pub use filter::Filter;
+
 /// Creates a new search with the given pattern using the Everything search
 /// syntax.
 ///
@@ -78,6 +85,35 @@ pub struct Search {
     ///
     /// By default, no additional metadata is included.
     pub requested_metadata: ItemMetadata,
+
+    /// Specifies a secondary sort key and order used to break ties in
+    /// [`Self::sort_key`] when building a [`Snapshot`] via
+    /// [`Self::query_snapshot`].
+    ///
+    /// `None` by default, meaning ties are left in whatever order Everything
+    /// returns them.
+    pub tie_break: Option<(SortKey, SortOrder)>,
+
+    /// Specifies a time budget for [`Self::query_with_budget`].
+    ///
+    /// `None` by default, meaning no time budget is applied.
+    pub timeout: Option<Duration>,
+
+    /// Specifies whether to reorder results so that folders are listed
+    /// before files, with volumes last, preserving the Everything-chosen
+    /// order within each group. `false` by default.
+    ///
+    /// This reordering only makes sense over a complete result set, so it
+    /// only takes effect in [`Self::query_snapshot`].
+    pub folders_first: bool,
+
+    /// Specifies whether to reverse the order of results. `false` by
+    /// default. The reversal is applied before [`Self::folders_first`], so
+    /// folders are still listed first when both are set.
+    ///
+    /// This reordering only makes sense over a complete result set, so it
+    /// only takes effect in [`Self::query_snapshot`].
+    pub reverse: bool,
 }
 
 /// Specifies the order in which search results are sorted.
@@ -146,6 +182,21 @@ impl Search {
         self
     }
 
+    /// Sets a secondary sort key and order used to break ties in
+    /// [`Self::sort_key`] when building a [`Snapshot`] via
+    /// [`Self::query_snapshot`], e.g. falling back to [`SortKey::Path`] to
+    /// guarantee a deterministic total order when many items share a primary
+    /// sort key. Not applied when [`Self::sort_key`] can't be rebuilt
+    /// client-side; see [`Self::query_snapshot`].
+    ///
+    /// By default, no tie-break is applied.
+    #[must_use]
+    #[inline]
+    pub const fn tie_break(mut self, key: SortKey, order: SortOrder) -> Self {
+        self.tie_break = Some((key, order));
+        self
+    }
+
     /// Requests additional file system metadata to be included in search results.
     /// This method can be called multiple times and the requested metadata will
     /// be combined.
@@ -159,6 +210,100 @@ impl Search {
         self.requested_metadata |= metadata;
         self
     }
+
+    /// Sets a time budget for [`Self::query_with_budget`].
+    ///
+    /// By default, no time budget is applied.
+    #[must_use]
+    #[inline]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether to reorder results so that folders are listed before
+    /// files, with volumes last. Only takes effect in [`Self::query_snapshot`].
+    ///
+    /// By default, folders are not reordered ahead of files.
+    #[must_use]
+    #[inline]
+    pub const fn folders_first(mut self, folders_first: bool) -> Self {
+        self.folders_first = folders_first;
+        self
+    }
+
+    /// Sets whether to reverse the order of results. Only takes effect in
+    /// [`Self::query_snapshot`], where it is applied before
+    /// [`Self::folders_first`].
+    ///
+    /// By default, results are not reversed.
+    #[must_use]
+    #[inline]
+    pub const fn reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+}
+
+/// The results of a time-budgeted query. See [`Search::query_with_budget`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchResults {
+    /// The items retrieved before the time budget was exhausted.
+    pub items: Vec<Item>,
+
+    /// `true` if the time budget was exhausted before all results were
+    /// retrieved, meaning [`Self::items`] is a partial result.
+    pub degraded: bool,
+}
+
+/// A frozen, fully-materialized set of search results, used for consistent
+/// pagination. See [`Search::query_snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snapshot {
+    items: Vec<Item>,
+}
+
+impl Snapshot {
+    /// Returns all items in this snapshot.
+    #[must_use]
+    #[inline]
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    /// Returns the items within the specified range of this snapshot.
+    ///
+    /// Unlike [`Search::query_range`], repeated calls over the same
+    /// [`Snapshot`] are always internally consistent, since the full result
+    /// set is fetched once upfront and frozen.
+    #[must_use]
+    pub fn page<R: RangeBounds<usize>>(&self, range: R) -> &[Item] {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => (end + 1).min(self.items.len()),
+            Bound::Excluded(&end) => end.min(self.items.len()),
+            Bound::Unbounded => self.items.len(),
+        };
+        &self.items[start.min(end)..end]
+    }
+
+    /// The total number of items in this snapshot.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if this snapshot has no items.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
 }
 
 /// Represents information about a file, folder or volume in the file system.
@@ -193,7 +338,17 @@ pub struct Item {
     /// The attributes of the item if available.
     /// `None` if the field was not requested via [`Search::request_metadata`]
     /// or is not available. In the latter case, the error is logged.
-    pub attributes: Option<u32>,
+    pub attributes: Option<FileAttributes>,
+
+    /// The number of times the item has been run, if available.
+    /// `None` if the field was not requested via [`Search::request_metadata`]
+    /// or is not available. In the latter case, the error is logged.
+    pub run_count: Option<u32>,
+
+    /// The date the item was last run, if available.
+    /// `None` if the field was not requested via [`Search::request_metadata`]
+    /// or is not available. In the latter case, the error is logged.
+    pub date_run: Option<SystemTime>,
 }
 
 /// Represents the type of the [`Item`].
@@ -219,6 +374,88 @@ bitflags::bitflags! {
             RequestFlags::EVERYTHING_REQUEST_DATE_ACCESSED.bits();
         const ATTRIBUTES =
             RequestFlags::EVERYTHING_REQUEST_ATTRIBUTES.bits();
+        const RUN_COUNT =
+            RequestFlags::EVERYTHING_REQUEST_RUN_COUNT.bits();
+        const DATE_RUN =
+            RequestFlags::EVERYTHING_REQUEST_DATE_RUN.bits();
+    }
+}
+
+bitflags::bitflags! {
+    /// Typed Windows file attributes (`FILE_ATTRIBUTE_*`).
+    ///
+    /// See the [`FILE_ATTRIBUTE` constants](https://learn.microsoft.com/en-us/windows/win32/fileio/file-attribute-constants)
+    /// for details on each flag.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[derive(Default)]
+    pub struct FileAttributes: u32 {
+        const READONLY = 0x1;
+        const HIDDEN = 0x2;
+        const SYSTEM = 0x4;
+        const DIRECTORY = 0x10;
+        const ARCHIVE = 0x20;
+        const REPARSE_POINT = 0x400;
+        const COMPRESSED = 0x800;
+        const ENCRYPTED = 0x4000;
+    }
+}
+
+/// Convenience predicates for commonly checked [`FileAttributes`].
+impl FileAttributes {
+    /// Returns `true` if the [`Self::READONLY`] attribute is set.
+    #[must_use]
+    #[inline]
+    pub fn is_readonly(self) -> bool {
+        self.contains(Self::READONLY)
+    }
+
+    /// Returns `true` if the [`Self::HIDDEN`] attribute is set.
+    #[must_use]
+    #[inline]
+    pub fn is_hidden(self) -> bool {
+        self.contains(Self::HIDDEN)
+    }
+
+    /// Returns `true` if the [`Self::SYSTEM`] attribute is set.
+    #[must_use]
+    #[inline]
+    pub fn is_system(self) -> bool {
+        self.contains(Self::SYSTEM)
+    }
+
+    /// Returns `true` if the [`Self::DIRECTORY`] attribute is set.
+    #[must_use]
+    #[inline]
+    pub fn is_directory(self) -> bool {
+        self.contains(Self::DIRECTORY)
+    }
+
+    /// Returns `true` if the [`Self::ARCHIVE`] attribute is set.
+    #[must_use]
+    #[inline]
+    pub fn is_archive(self) -> bool {
+        self.contains(Self::ARCHIVE)
+    }
+
+    /// Returns `true` if the [`Self::REPARSE_POINT`] attribute is set.
+    #[must_use]
+    #[inline]
+    pub fn is_reparse_point(self) -> bool {
+        self.contains(Self::REPARSE_POINT)
+    }
+
+    /// Returns `true` if the [`Self::COMPRESSED`] attribute is set.
+    #[must_use]
+    #[inline]
+    pub fn is_compressed(self) -> bool {
+        self.contains(Self::COMPRESSED)
+    }
+
+    /// Returns `true` if the [`Self::ENCRYPTED`] attribute is set.
+    #[must_use]
+    #[inline]
+    pub fn is_encrypted(self) -> bool {
+        self.contains(Self::ENCRYPTED)
     }
 }
 
@@ -260,7 +497,8 @@ impl Search {
     /// guaranteed to be consistent â€” files may be added, removed, or reordered
     /// between calls, causing gaps or overlaps. This limitation is inherent to
     /// the Everything indexing system. To get consistent results, fetch everything
-    /// you need in a single call.
+    /// you need in a single call, or use [`Self::query_snapshot`] for a frozen,
+    /// consistently paginated result set.
     #[must_use]
     pub fn query_range<R: RangeBounds<usize>>(&self, range: R) -> Vec<Item> {
         let range_start = match range.start_bound() {
@@ -288,6 +526,153 @@ impl Search {
             .collect()
     }
 
+    /// Executes the search and returns results within the configured
+    /// [`Self::timeout`], stopping early once the budget is exhausted.
+    ///
+    /// Results are fetched incrementally in fixed-size chunks so that elapsed
+    /// time can be checked between fetches, instead of blocking on the whole
+    /// range like [`Self::query_all`]. If no [`Self::timeout`] is set, this
+    /// behaves like [`Self::query_all`] and [`SearchResults::degraded`] is
+    /// always `false`.
+    ///
+    /// This method blocks until either all results are retrieved or the time
+    /// budget is exhausted. To avoid blocking, consider spawning a separate
+    /// thread for the search.
+    #[must_use]
+    pub fn query_with_budget(&self) -> SearchResults {
+        const CHUNK_SIZE: u32 = 1000;
+
+        let Some(timeout) = self.timeout else {
+            return SearchResults { items: self.query_all(), degraded: false };
+        };
+
+        let deadline = Instant::now() + timeout;
+        let total = self.count();
+        let mut items = Vec::new();
+        let mut offset = 0_u32;
+
+        loop {
+            // Checked before the deadline so that a result set fully fetched
+            // by a preceding chunk (even one that exactly fills CHUNK_SIZE)
+            // isn't reported as degraded just because the budget ran out
+            // before a confirming empty fetch.
+            if items.len() as u32 >= total {
+                return SearchResults { items, degraded: false };
+            }
+
+            if Instant::now() >= deadline {
+                return SearchResults { items, degraded: true };
+            }
+
+            let mut everything = everything_sdk::global().lock().unwrap();
+            let mut searcher = everything.searcher();
+            self.apply(&mut searcher);
+            let result =
+                searcher
+                    .set_offset(offset)
+                    .set_max(CHUNK_SIZE)
+                    .query();
+            let fetched = result.num();
+            items.extend((0..fetched).filter_map(|i| Item::from_result(self, &result, i)));
+            drop(everything);
+
+            if fetched == 0 {
+                return SearchResults { items, degraded: false };
+            }
+            offset += CHUNK_SIZE;
+        }
+    }
+
+    /// Executes the search and returns a frozen [`Snapshot`] of all matching
+    /// items.
+    ///
+    /// Unlike [`Self::query_range`], repeated accesses into the returned
+    /// [`Snapshot`] are guaranteed to be internally consistent, since the
+    /// full result set is fetched once upfront rather than re-querying the
+    /// live index for each page. If [`Self::tie_break`] is set, items are
+    /// additionally sorted by it client-side, so that items tying on
+    /// [`Self::sort_key`] are ordered deterministically. This client-side
+    /// sort is skipped, keeping Everything's order, when [`Self::sort_key`]
+    /// can't be rebuilt from [`Item`] fields: for [`SortKey::TypeName`], and
+    /// for metadata-backed keys whose metadata was not requested via
+    /// [`Self::request_metadata`].
+    /// [`Self::reverse`] and [`Self::folders_first`] are then applied to the
+    /// frozen set, in that order, so that folders are listed first
+    /// regardless of whether [`Self::reverse`] is also set.
+    ///
+    /// This method blocks until all results are retrieved. To avoid blocking,
+    /// consider spawning a separate thread for the search.
+    #[must_use]
+    pub fn query_snapshot(&self) -> Snapshot {
+        let mut items = self.query_all();
+
+        if let Some((tie_break_key, tie_break_order)) = self.tie_break {
+            // Re-sorting client-side requires reconstructing the primary key
+            // from `Item` fields. If we can't do that faithfully (e.g. the
+            // key is `TypeName`, or its metadata wasn't requested and is
+            // `None` for every item), skip the re-sort entirely rather than
+            // let every item compare equal on the primary key and have the
+            // tie-break silently become the de facto sort.
+            if self.can_reproduce_sort_key(self.sort_key) {
+                items.sort_by(|left, right| {
+                    compare_by_key(left, right, self.sort_key, self.sort_order)
+                        .then_with(|| compare_by_key(left, right, tie_break_key, tie_break_order))
+                });
+            }
+        }
+
+        if self.reverse {
+            items.reverse();
+        }
+
+        // Applied after `reverse` so that folders are always listed first,
+        // regardless of whether the rest of the order was reversed.
+        if self.folders_first {
+            items.sort_by_key(|item| match item.item_type {
+                ItemType::Folder => 0,
+                ItemType::File => 1,
+                ItemType::Volume => 2,
+            });
+        }
+
+        Snapshot { items }
+    }
+
+    /// Returns the total number of items matching this search, without
+    /// materializing most of them.
+    ///
+    /// This issues the search with a minimal fetch window and reads
+    /// Everything's total-results counter, which is far cheaper than calling
+    /// `query_all().len()` when all that's needed is a count, e.g. for
+    /// pagination UIs or progress reporting.
+    #[must_use]
+    pub fn count(&self) -> u32 {
+        let mut everything = everything_sdk::global().lock().unwrap();
+        let mut searcher = everything.searcher();
+        self.apply(&mut searcher);
+        searcher.set_offset(0).set_max(0).query().total()
+    }
+
+    /// Returns `true` if `key` can be faithfully reconstructed client-side
+    /// from [`Item`] fields, as needed by [`compare_by_key`] in
+    /// [`Self::query_snapshot`].
+    ///
+    /// `SortKey::TypeName` has no corresponding `Item` field. The metadata-
+    /// backed keys additionally require that their metadata was actually
+    /// requested via [`Self::request_metadata`], otherwise every item's
+    /// field is `None` and the key can't discriminate between them.
+    fn can_reproduce_sort_key(&self, key: SortKey) -> bool {
+        match key {
+            SortKey::TypeName => false,
+            SortKey::Name | SortKey::Path | SortKey::Extension => true,
+            SortKey::Size => self.requested_metadata.contains(ItemMetadata::SIZE),
+            SortKey::DateCreated => self.requested_metadata.contains(ItemMetadata::DATE_CREATED),
+            SortKey::DateModified => self.requested_metadata.contains(ItemMetadata::DATE_MODIFIED),
+            SortKey::DateAccessed => self.requested_metadata.contains(ItemMetadata::DATE_ACCESSED),
+            SortKey::Attributes => self.requested_metadata.contains(ItemMetadata::ATTRIBUTES),
+        }
+    }
+
     fn apply(&self, searcher: &mut EverythingSearcher) {
         searcher
             .set_search(&self.pattern)
@@ -376,7 +761,21 @@ impl Item {
                     &item,
                     &path,
                     ItemMetadata::ATTRIBUTES,
-                    EverythingItem::attributes),
+                    EverythingItem::attributes).map(FileAttributes::from_bits_retain),
+            run_count:
+                get_metadata_from_item(
+                    search,
+                    &item,
+                    &path,
+                    ItemMetadata::RUN_COUNT,
+                    EverythingItem::run_count),
+            date_run:
+                get_metadata_from_item(
+                    search,
+                    &item,
+                    &path,
+                    ItemMetadata::DATE_RUN,
+                    EverythingItem::date_run).map(convert_filetime),
         })
     }
 }
@@ -424,6 +823,46 @@ fn convert_filetime(filetime: u64) -> SystemTime {
     SystemTime::UNIX_EPOCH + Duration::new(secs, nanos)
 }
 
+/// Compares two [`Item`]s by the field corresponding to `key`, in `order`.
+///
+/// Used to reproduce Everything's sort order client-side on a [`Snapshot`],
+/// so that a secondary tie-break key can be applied deterministically.
+/// [`SortKey::TypeName`] has no corresponding [`Item`] field and is treated
+/// as always equal.
+fn compare_by_key(left: &Item, right: &Item, key: SortKey, order: SortOrder) -> std::cmp::Ordering {
+    let ordering = match key {
+        SortKey::Name =>
+            lowercase(left.path.file_name()).cmp(&lowercase(right.path.file_name())),
+        SortKey::Path =>
+            left.path.cmp(&right.path),
+        SortKey::Size =>
+            left.size.cmp(&right.size),
+        SortKey::Extension =>
+            lowercase(left.path.extension()).cmp(&lowercase(right.path.extension())),
+        SortKey::DateCreated =>
+            left.date_created.cmp(&right.date_created),
+        SortKey::DateModified =>
+            left.date_modified.cmp(&right.date_modified),
+        SortKey::DateAccessed =>
+            left.date_accessed.cmp(&right.date_accessed),
+        SortKey::Attributes =>
+            left.attributes.map(FileAttributes::bits).cmp(&right.attributes.map(FileAttributes::bits)),
+        SortKey::TypeName =>
+            std::cmp::Ordering::Equal,
+    };
+
+    match order {
+        SortOrder::Ascending => ordering,
+        SortOrder::Descending => ordering.reverse(),
+    }
+}
+
+/// Lowercases an optional path component, matching Everything's
+/// case-insensitive collation for [`SortKey::Name`] and [`SortKey::Extension`].
+fn lowercase(component: Option<&std::ffi::OsStr>) -> Option<String> {
+    component.map(|component| component.to_string_lossy().to_lowercase())
+}
+
 /// Combines the given [`SortKey`] and [`SortOrder`] into the corresponding
 /// [`SortType`] used by [`everything_sdk`].
 #[expect(clippy::enum_glob_use, reason = "Using glob imports improves readability in this match statement.")]